@@ -20,8 +20,10 @@
 //!
 //! ## Important notes
 //!
-//! - This is **not OS-level TCP keepalive** (it does not send keepalive probes).
-//!   “Alive” here means **traffic passed through** the bridge.
+//! - `connect()` itself does **not** send OS-level keepalive probes.
+//!   “Alive” here means **traffic passed through** the bridge. If you also want
+//!   true link-death detection via OS keepalive probes, use
+//!   [`connect_with`] with a [`KeepaliveConfig`].
 //! - `connect()` is **blocking**: it returns when either side closes, an I/O error occurs,
 //!   or the traffic-based idle timeout triggers.
 //! - The implementation uses blocking I/O + `std::thread`.
@@ -64,35 +66,223 @@
 //! }
 //! ```
 
-use std::io::{Read, Result, Write};
+use std::io::{self, Read, Result, Write};
 use std::net::{Shutdown, TcpStream};
 use std::sync::{
     Arc,
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering},
 };
 use std::thread;
 use std::time::Duration;
 
+use socket2::{Socket, TcpKeepalive};
+
+/// OS-level TCP keepalive probe configuration, applied directly to the
+/// underlying sockets.
+///
+/// This is distinct from the crate's traffic-based idle timeout described in
+/// the module docs: keepalive probes detect a dead peer even when the bridge
+/// itself has nothing to send, while the idle timeout only reacts to the
+/// *absence* of relayed application traffic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepaliveConfig {
+    /// Idle period before the first probe is sent.
+    pub time: Option<Duration>,
+    /// Spacing between subsequent probes.
+    ///
+    /// Only applied on platforms socket2's `with_interval` supports (Linux,
+    /// most BSDs, Apple platforms, Windows); ignored elsewhere.
+    pub interval: Option<Duration>,
+    /// Number of unacknowledged probes before the OS drops the connection.
+    ///
+    /// Only applied on platforms socket2's `with_retries` supports (Linux,
+    /// most BSDs, Apple platforms); ignored elsewhere, including Windows and
+    /// OpenBSD.
+    pub retries: Option<u32>,
+}
+
+/// Minimum relay buffer size, in bytes.
+///
+/// [`BridgeOptions::buffer_size`] is clamped up to this. A read into a
+/// `0`-byte buffer returns `Ok(0)`, which the relay loop takes for
+/// end-of-stream, so an unclamped `0` would close the bridge at once; a very
+/// small buffer would relay only a few bytes per read.
+pub const MIN_BUFFER_SIZE: usize = 1024;
+
+/// Per-socket behavior tuning for a bridge.
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeOptions {
+    /// Disable Nagle's algorithm (`TCP_NODELAY`) on both sockets.
+    ///
+    /// Worth enabling for latency-sensitive, small-payload traffic relayed
+    /// through the bridge; leave off for bulk/throughput-oriented transfers.
+    pub nodelay: bool,
+    /// Size in bytes of the buffer each relay thread reads into.
+    ///
+    /// Clamped up to [`MIN_BUFFER_SIZE`].
+    pub buffer_size: usize,
+}
+
+impl Default for BridgeOptions {
+    fn default() -> Self {
+        BridgeOptions {
+            nodelay: false,
+            buffer_size: 16 * 1024,
+        }
+    }
+}
+
+fn apply_keepalive(stream: &TcpStream, cfg: &KeepaliveConfig) -> Result<()> {
+    let socket = Socket::from(stream.try_clone()?);
+    let mut keepalive = TcpKeepalive::new();
+    if let Some(time) = cfg.time {
+        keepalive = keepalive.with_time(time);
+    }
+
+    // `TcpKeepalive::with_interval`/`with_retries` aren't gated identically:
+    // match socket2's own per-method platform lists instead of guessing a
+    // shared one. `with_retries` is also only compiled into socket2 with its
+    // `all` Cargo feature (enabled in our Cargo.toml) and isn't implemented
+    // for Windows or OpenBSD at all.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "fuchsia",
+        target_os = "illumos",
+        target_os = "ios",
+        target_os = "visionos",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    if let Some(interval) = cfg.interval {
+        keepalive = keepalive.with_interval(interval);
+    }
+
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "fuchsia",
+        target_os = "illumos",
+        target_os = "ios",
+        target_os = "visionos",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    if let Some(retries) = cfg.retries {
+        keepalive = keepalive.with_retries(retries);
+    }
+
+    socket.set_tcp_keepalive(&keepalive)?;
+    Ok(())
+}
+
+/// Why a bridge run via [`connect_with_stats`] terminated.
+///
+/// Not tracked by the plain [`connect`]/[`connect_with`] entry points, which
+/// discard it the same way they always have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosedReason {
+    /// One side of the bridge closed its connection (a read returned EOF).
+    PeerClosed,
+    /// A relay thread hit an I/O error other than EOF or a read/write timeout.
+    IoError,
+    /// The traffic-based idle timeout elapsed without traffic having been
+    /// relayed in both directions (one idle direction is enough).
+    IdleTimeout,
+    /// The bridge was stopped deliberately via [`BridgeHandle::shutdown`].
+    Cancelled,
+}
+
+const REASON_NONE: u8 = 0;
+const REASON_PEER_CLOSED: u8 = 1;
+const REASON_IO_ERROR: u8 = 2;
+const REASON_IDLE_TIMEOUT: u8 = 3;
+const REASON_CANCELLED: u8 = 4;
+
+fn store_reason(reason: &AtomicU8, value: u8) {
+    // First reason wins: once one thread (or the main loop) has recorded why
+    // the bridge is closing, later closers racing to shut the same bridge
+    // down don't get to overwrite it.
+    reason
+        .compare_exchange(REASON_NONE, value, Ordering::AcqRel, Ordering::Acquire)
+        .ok();
+}
+
 fn stream(
     closed: Arc<AtomicBool>,
     mut reader: TcpStream,
     mut writer: TcpStream,
     ping: Arc<AtomicBool>,
+    bytes: Arc<AtomicU64>,
+    reason: Arc<AtomicU8>,
+    buffer_size: usize,
 ) {
-    let mut buf: Vec<u8> = vec![0u8; 16 * 1024];
+    let mut buf: Vec<u8> = vec![0u8; buffer_size];
     loop {
         if closed.load(Ordering::Relaxed) {
             break;
         } else {
             match reader.read(&mut buf) {
-                Ok(0) => break,
+                Ok(0) => {
+                    store_reason(&reason, REASON_PEER_CLOSED);
+                    break;
+                }
                 Ok(n) => {
-                    if writer.write(&buf[..n]).is_err() {
+                    // Loop on short writes instead of a plain `write`, which
+                    // can accept fewer than `n` bytes and would otherwise
+                    // silently drop the remainder from the relayed stream
+                    // while still counting it as delivered. A write timeout
+                    // is not fatal (the writer's socket has the same
+                    // `rate_check_seconds` timeout as the reader) — it just
+                    // means the peer's receive window is full for now, so it
+                    // retries like the read side does.
+                    let mut written = 0;
+                    let write_error = loop {
+                        if written == n {
+                            break false;
+                        }
+                        match writer.write(&buf[written..n]) {
+                            Ok(0) => break true,
+                            Ok(m) => written += m,
+                            Err(e)
+                                if matches!(
+                                    e.kind(),
+                                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                                ) =>
+                            {
+                                if closed.load(Ordering::Relaxed) {
+                                    break true;
+                                }
+                            }
+                            Err(_) => break true,
+                        }
+                    };
+                    if write_error {
+                        store_reason(&reason, REASON_IO_ERROR);
                         break;
                     }
+                    bytes.fetch_add(n as u64, Ordering::Relaxed);
                     ping.store(true, Ordering::Release);
                 }
-                Err(_) => break,
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                    // No traffic this round; loop back around and re-check
+                    // the shared `closed` flag instead of wedging forever on
+                    // a blocking read.
+                    continue;
+                }
+                Err(_) => {
+                    store_reason(&reason, REASON_IO_ERROR);
+                    break;
+                }
             }
         }
     }
@@ -101,17 +291,28 @@ fn stream(
     reader.shutdown(Shutdown::Both).ok();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn config_stream(
     closed: &Arc<AtomicBool>,
     stream1: &TcpStream,
     stream2: &TcpStream,
     ping1: &Arc<AtomicBool>,
+    read_timeout: Option<Duration>,
+    bytes: &Arc<AtomicU64>,
+    reason: &Arc<AtomicU8>,
+    options: &BridgeOptions,
 ) -> Result<()> {
-    let closed = Arc::clone(&closed);
-    let ping1 = Arc::clone(&ping1);
+    let closed = Arc::clone(closed);
+    let ping1 = Arc::clone(ping1);
+    let bytes = Arc::clone(bytes);
+    let reason = Arc::clone(reason);
     let stream1 = stream1.try_clone()?;
     let stream2 = stream2.try_clone()?;
-    thread::spawn(move || stream(closed, stream1, stream2, ping1));
+    stream1.set_read_timeout(read_timeout)?;
+    stream1.set_write_timeout(read_timeout)?;
+    stream1.set_nodelay(options.nodelay)?;
+    let buffer_size = options.buffer_size;
+    thread::spawn(move || stream(closed, stream1, stream2, ping1, bytes, reason, buffer_size));
     Ok(())
 }
 
@@ -126,7 +327,8 @@ fn config_stream(
 /// This function does **not** send any ping/keepalive packets.
 /// It only considers the connection “active” when data is successfully relayed.
 ///
-/// If you need true TCP keepalive probes, configure them on the sockets
+/// If you need true TCP keepalive probes, use [`connect_with`] with a
+/// [`KeepaliveConfig`] instead, or configure them on the sockets yourself
 /// before calling this function.
 ///
 /// ## Parameters
@@ -154,15 +356,164 @@ fn config_stream(
 pub fn connect(
     stream1: TcpStream,
     stream2: TcpStream,
-    mut rate_check_seconds: u8,
-    mut keep_alive_delay_time_seconds: u64,
+    rate_check_seconds: u8,
+    keep_alive_delay_time_seconds: u64,
+) -> Result<()> {
+    connect_with(
+        stream1,
+        stream2,
+        rate_check_seconds,
+        keep_alive_delay_time_seconds,
+        None,
+        BridgeOptions::default(),
+    )
+}
+
+/// Same as [`connect`], but additionally accepts an OS-level
+/// [`KeepaliveConfig`] applied to both sockets before the relay threads are
+/// spawned, and [`BridgeOptions`] for `TCP_NODELAY`/buffer size tuning.
+///
+/// Pass `None` to skip OS keepalive entirely and rely solely on the
+/// traffic-based idle timeout, which is what [`connect`] does.
+pub fn connect_with(
+    stream1: TcpStream,
+    stream2: TcpStream,
+    rate_check_seconds: u8,
+    keep_alive_delay_time_seconds: u64,
+    keepalive: Option<KeepaliveConfig>,
+    options: BridgeOptions,
 ) -> Result<()> {
+    bridge(
+        stream1,
+        stream2,
+        rate_check_seconds,
+        keep_alive_delay_time_seconds,
+        keepalive,
+        options,
+        Arc::new(AtomicBool::new(false)),
+        Arc::new(AtomicU8::new(REASON_NONE)),
+    )
+    .map(|_stats| ())
+}
+
+/// Byte counts and termination reason for a bridge run via
+/// [`connect_with_stats`].
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeStats {
+    /// Total bytes relayed from `stream1` to `stream2`.
+    pub bytes_a_to_b: u64,
+    /// Total bytes relayed from `stream2` to `stream1`.
+    pub bytes_b_to_a: u64,
+    /// Why the bridge terminated.
+    pub closed_reason: ClosedReason,
+}
+
+/// Same as [`connect_with`], but returns [`BridgeStats`] (bytes relayed in
+/// each direction and why the bridge terminated) instead of discarding them.
+pub fn connect_with_stats(
+    stream1: TcpStream,
+    stream2: TcpStream,
+    rate_check_seconds: u8,
+    keep_alive_delay_time_seconds: u64,
+    keepalive: Option<KeepaliveConfig>,
+    options: BridgeOptions,
+) -> Result<BridgeStats> {
+    bridge(
+        stream1,
+        stream2,
+        rate_check_seconds,
+        keep_alive_delay_time_seconds,
+        keepalive,
+        options,
+        Arc::new(AtomicBool::new(false)),
+        Arc::new(AtomicU8::new(REASON_NONE)),
+    )
+}
+
+/// Lightweight handle for cooperatively cancelling a bridge started via
+/// [`connect_cancellable`] from another thread.
+///
+/// Calling [`shutdown`](BridgeHandle::shutdown) sets the same `closed` flag
+/// the bridge's relay threads and idle-timeout loop already check, records
+/// [`ClosedReason::Cancelled`], then shuts both sockets down, so there's no
+/// race between the handle and the bridge over who closes the connection or
+/// why.
+pub struct BridgeHandle {
+    closed: Arc<AtomicBool>,
+    reason: Arc<AtomicU8>,
+    stream1: TcpStream,
+    stream2: TcpStream,
+}
+
+impl BridgeHandle {
+    /// Signal the bridge to stop and shut down both underlying sockets.
+    ///
+    /// Safe to call from any thread, at any time, including after the
+    /// bridge has already terminated on its own.
+    pub fn shutdown(&self) {
+        store_reason(&self.reason, REASON_CANCELLED);
+        self.closed.store(true, Ordering::Release);
+        self.stream1.shutdown(Shutdown::Both).ok();
+        self.stream2.shutdown(Shutdown::Both).ok();
+    }
+}
+
+/// Same as [`connect_with_stats`], but runs the bridge on its own thread and
+/// immediately returns a [`BridgeHandle`] for cancelling it from elsewhere
+/// (e.g. on `SIGTERM`, or when draining a proxy) alongside a `JoinHandle`
+/// to wait for the final [`BridgeStats`].
+pub fn connect_cancellable(
+    stream1: TcpStream,
+    stream2: TcpStream,
+    rate_check_seconds: u8,
+    keep_alive_delay_time_seconds: u64,
+    keepalive: Option<KeepaliveConfig>,
+    options: BridgeOptions,
+) -> Result<(BridgeHandle, thread::JoinHandle<Result<BridgeStats>>)> {
     let closed = Arc::new(AtomicBool::new(false));
+    let reason = Arc::new(AtomicU8::new(REASON_NONE));
+    let handle = BridgeHandle {
+        closed: Arc::clone(&closed),
+        reason: Arc::clone(&reason),
+        stream1: stream1.try_clone()?,
+        stream2: stream2.try_clone()?,
+    };
+    let join = thread::spawn(move || {
+        bridge(
+            stream1,
+            stream2,
+            rate_check_seconds,
+            keep_alive_delay_time_seconds,
+            keepalive,
+            options,
+            closed,
+            reason,
+        )
+    });
+    Ok((handle, join))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bridge(
+    stream1: TcpStream,
+    stream2: TcpStream,
+    mut rate_check_seconds: u8,
+    mut keep_alive_delay_time_seconds: u64,
+    keepalive: Option<KeepaliveConfig>,
+    mut options: BridgeOptions,
+    closed: Arc<AtomicBool>,
+    reason: Arc<AtomicU8>,
+) -> Result<BridgeStats> {
+    if let Some(cfg) = &keepalive {
+        apply_keepalive(&stream1, cfg)?;
+        apply_keepalive(&stream2, cfg)?;
+    }
+    options.buffer_size = options.buffer_size.max(MIN_BUFFER_SIZE);
+
     let ping1 = Arc::new(AtomicBool::new(false));
     let ping2 = Arc::new(AtomicBool::new(false));
-
-    config_stream(&closed, &stream1, &stream2, &ping1)?;
-    config_stream(&closed, &stream2, &stream1, &ping2)?;
+    let bytes_a_to_b = Arc::new(AtomicU64::new(0));
+    let bytes_b_to_a = Arc::new(AtomicU64::new(0));
 
     if rate_check_seconds < 1 {
         rate_check_seconds = 1
@@ -171,9 +522,34 @@ pub fn connect(
         keep_alive_delay_time_seconds = 2
     }
 
+    let rate_check = Duration::from_secs(rate_check_seconds as u64);
+
+    // Relay threads use `rate_check` as their read and write timeout, so a
+    // silent peer never wedges a thread on a blocking read: it wakes up,
+    // observes `closed`, and exits once the idle-timeout logic below fires.
+    config_stream(
+        &closed,
+        &stream1,
+        &stream2,
+        &ping1,
+        Some(rate_check),
+        &bytes_a_to_b,
+        &reason,
+        &options,
+    )?;
+    config_stream(
+        &closed,
+        &stream2,
+        &stream1,
+        &ping2,
+        Some(rate_check),
+        &bytes_b_to_a,
+        &reason,
+        &options,
+    )?;
+
     let mut delay: u64 = 0;
     let max_delay = keep_alive_delay_time_seconds / rate_check_seconds as u64;
-    let rate_check = Duration::from_secs(rate_check_seconds as u64);
     loop {
         if ping1.load(Ordering::Acquire) && ping2.load(Ordering::Acquire) {
             ping1.store(false, Ordering::Release);
@@ -181,6 +557,7 @@ pub fn connect(
             delay = 0; // reset delay count
         } else {
             if delay > max_delay {
+                store_reason(&reason, REASON_IDLE_TIMEOUT);
                 closed.store(true, Ordering::Release);
                 stream1.shutdown(Shutdown::Both).ok();
                 stream2.shutdown(Shutdown::Both).ok();
@@ -193,5 +570,112 @@ pub fn connect(
             }
         }
     }
-    Ok(())
+
+    let closed_reason = match reason.load(Ordering::Acquire) {
+        REASON_IO_ERROR => ClosedReason::IoError,
+        REASON_IDLE_TIMEOUT => ClosedReason::IdleTimeout,
+        REASON_CANCELLED => ClosedReason::Cancelled,
+        _ => ClosedReason::PeerClosed,
+    };
+
+    Ok(BridgeStats {
+        bytes_a_to_b: bytes_a_to_b.load(Ordering::Acquire),
+        bytes_b_to_a: bytes_b_to_a.load(Ordering::Acquire),
+        closed_reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::time::Instant;
+
+    /// Connects a fresh loopback `TcpStream` pair: writes to `.0` are
+    /// readable on `.1` and vice versa.
+    fn loopback_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (client, server)
+    }
+
+    #[test]
+    fn idle_bridge_with_no_traffic_times_out_promptly() {
+        let (_peer_a, stream1) = loopback_pair();
+        let (_peer_b, stream2) = loopback_pair();
+
+        let start = Instant::now();
+        let stats =
+            connect_with_stats(stream1, stream2, 1, 2, None, BridgeOptions::default()).unwrap();
+
+        // With a 1s rate check and a 2s idle limit, `max_delay` is 2 and the
+        // main loop gives up after three idle rounds (about 3s); it returns
+        // without waiting for the relay threads to exit.
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(stats.closed_reason, ClosedReason::IdleTimeout);
+        assert_eq!(stats.bytes_a_to_b, 0);
+        assert_eq!(stats.bytes_b_to_a, 0);
+    }
+
+    #[test]
+    fn bridge_stats_byte_counts_match_bytes_actually_relayed() {
+        let (mut peer_a, stream1) = loopback_pair();
+        let (mut peer_b, stream2) = loopback_pair();
+
+        let payload_a_to_b = vec![1u8; 4096];
+        let payload_b_to_a = vec![2u8; 2048];
+        peer_a.write_all(&payload_a_to_b).unwrap();
+        peer_b.write_all(&payload_b_to_a).unwrap();
+
+        let stats =
+            connect_with_stats(stream1, stream2, 1, 2, None, BridgeOptions::default()).unwrap();
+
+        assert_eq!(stats.bytes_a_to_b, payload_a_to_b.len() as u64);
+        assert_eq!(stats.bytes_b_to_a, payload_b_to_a.len() as u64);
+    }
+
+    #[test]
+    fn bridge_handle_shutdown_stops_a_running_bridge_promptly() {
+        let (_peer_a, stream1) = loopback_pair();
+        let (_peer_b, stream2) = loopback_pair();
+
+        // Use a short rate-check so the main loop notices the shutdown
+        // quickly, and a long idle timeout so it can't fire first and
+        // mask the cancellation we're testing for.
+        let (handle, join) =
+            connect_cancellable(stream1, stream2, 1, 7_200, None, BridgeOptions::default())
+                .unwrap();
+
+        let start = Instant::now();
+        handle.shutdown();
+        let stats = join.join().unwrap().unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(stats.closed_reason, ClosedReason::Cancelled);
+    }
+
+    #[test]
+    fn zero_buffer_size_is_clamped_and_still_relays() {
+        let (mut peer_a, stream1) = loopback_pair();
+        let (_peer_b, stream2) = loopback_pair();
+
+        let payload = vec![7u8; 4096];
+        peer_a.write_all(&payload).unwrap();
+
+        let options = BridgeOptions {
+            buffer_size: 0,
+            ..BridgeOptions::default()
+        };
+        let start = Instant::now();
+        let stats = connect_with_stats(stream1, stream2, 1, 2, None, options).unwrap();
+
+        // A `buffer_size` of `0` would make every read into the empty slice
+        // return `Ok(0)`, which the relay loop takes for end-of-stream: the
+        // bridge would close at once without relaying any bytes.
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(stats.bytes_a_to_b, payload.len() as u64);
+    }
+
 }